@@ -0,0 +1,94 @@
+use crate::build_cache::package_cached;
+use crate::CargoProfile;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Cross-compiles the crate rooted at `crate_dir` and assembles it into a buildpack directory.
+///
+/// The result is cached by a fingerprint of the crate's inputs, target triple and profile, so a
+/// subsequent test with unchanged sources reuses the artifact and skips the `cargo build` entirely.
+///
+/// Returns the path to the packaged buildpack, ready to be passed to `pack build --buildpack`.
+///
+/// # Panics
+/// - When the `cargo build` invocation fails.
+/// - When the compiled artifact or `buildpack.toml` could not be assembled.
+pub(crate) fn package_crate_buildpack(
+    crate_dir: &Path,
+    target_triple: &str,
+    cargo_profile: CargoProfile,
+) -> PathBuf {
+    let cache_dir = crate_dir.join("target").join("libcnb-test");
+
+    package_cached(
+        &cache_dir,
+        crate_dir,
+        target_triple,
+        cargo_profile,
+        |buildpack_dir| {
+            compile(crate_dir, target_triple, cargo_profile)?;
+            assemble(crate_dir, target_triple, cargo_profile, buildpack_dir)
+        },
+    )
+    .expect("Could not package crate-under-test buildpack")
+}
+
+fn compile(
+    crate_dir: &Path,
+    target_triple: &str,
+    cargo_profile: CargoProfile,
+) -> std::io::Result<()> {
+    let status = Command::new("cargo")
+        .arg("build")
+        .args(["--target", target_triple])
+        // Threads the selected profile into the build so the artifact matches how the buildpack
+        // ships, e.g. `--release` for an optimized binary.
+        .args(cargo_profile.cargo_args())
+        .current_dir(crate_dir)
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other("cargo build failed"))
+    }
+}
+
+fn assemble(
+    crate_dir: &Path,
+    target_triple: &str,
+    cargo_profile: CargoProfile,
+    buildpack_dir: &Path,
+) -> std::io::Result<()> {
+    let bin_dir = buildpack_dir.join("bin");
+    std::fs::create_dir_all(&bin_dir)?;
+
+    std::fs::copy(
+        crate_dir.join("buildpack.toml"),
+        buildpack_dir.join("buildpack.toml"),
+    )?;
+
+    let profile_dir = match cargo_profile {
+        CargoProfile::Dev => "debug",
+        CargoProfile::Release => "release",
+    };
+    let compiled = crate_dir
+        .join("target")
+        .join(target_triple)
+        .join(profile_dir)
+        .join(crate_name(crate_dir));
+    std::fs::copy(compiled, bin_dir.join("build"))?;
+
+    Ok(())
+}
+
+/// Reads the package name from the crate's `Cargo.toml`.
+fn crate_name(crate_dir: &Path) -> String {
+    let manifest = std::fs::read_to_string(crate_dir.join("Cargo.toml")).unwrap_or_default();
+    manifest
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("name"))
+        .and_then(|rest| rest.split('=').nth(1))
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .expect("Could not determine crate name from Cargo.toml")
+}
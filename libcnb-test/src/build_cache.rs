@@ -0,0 +1,319 @@
+use crate::CargoProfile;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A fingerprint of the inputs that produce a packaged buildpack.
+///
+/// Computed the way Cargo does freshness detection: the newest mtime across all input files plus a
+/// content hash of `Cargo.toml`/`Cargo.lock`, scoped to the selected target triple and profile.
+/// Persisted next to the cached buildpack artifact in a stamp file so that subsequent test runs can
+/// decide whether a recompile is needed.
+#[derive(Eq, PartialEq, Debug)]
+pub(crate) struct BuildpackFingerprint {
+    /// The newest mtime across all input files, as seconds since the Unix epoch.
+    max_mtime: u64,
+    /// A content hash of `Cargo.toml` and `Cargo.lock`.
+    manifest_hash: u64,
+    target_triple: String,
+    cargo_profile: CargoProfile,
+}
+
+impl BuildpackFingerprint {
+    /// Computes the fingerprint for the crate rooted at `crate_dir`.
+    ///
+    /// Returns `None` if any input file's mtime could not be determined, which forces an
+    /// unconditional rebuild (we cannot prove freshness without mtimes).
+    pub(crate) fn compute(
+        crate_dir: &Path,
+        target_triple: &str,
+        cargo_profile: CargoProfile,
+    ) -> Option<BuildpackFingerprint> {
+        let mut inputs = Vec::new();
+        collect_sources(&crate_dir.join("src"), &mut inputs);
+        for file in ["Cargo.toml", "Cargo.lock", "buildpack.toml"] {
+            let path = crate_dir.join(file);
+            if path.is_file() {
+                inputs.push(path);
+            }
+        }
+
+        let mut max_mtime = 0;
+        for input in &inputs {
+            max_mtime = max_mtime.max(mtime_secs(input)?);
+        }
+
+        let mut hasher = DefaultHasher::new();
+        for file in ["Cargo.toml", "Cargo.lock"] {
+            std::fs::read(crate_dir.join(file))
+                .unwrap_or_default()
+                .hash(&mut hasher);
+        }
+
+        Some(BuildpackFingerprint {
+            max_mtime,
+            manifest_hash: hasher.finish(),
+            target_triple: target_triple.to_string(),
+            cargo_profile,
+        })
+    }
+
+    /// Whether the buildpack cached behind `stamp_path` is still fresh for these inputs.
+    ///
+    /// The stamp file is written strictly *after* the inputs it describes, so its own mtime is the
+    /// moment the cached artifact was produced. The cache is fresh when the target triple, profile
+    /// and manifest hash recorded in the stamp all match and every input predates the stamp. Because
+    /// some filesystems have coarse (one second) mtime granularity, an input mtime *equal to or
+    /// newer than* the stamp's mtime is treated as dirty to avoid missing same-second edits. A
+    /// missing or unreadable stamp mtime also counts as dirty.
+    pub(crate) fn is_fresh(&self, stamp_path: &Path) -> bool {
+        let Some(stamp) = BuildpackFingerprint::read(stamp_path) else {
+            return false;
+        };
+        let Some(stamp_mtime) = mtime_secs(stamp_path) else {
+            return false;
+        };
+
+        self.is_fresh_against(&stamp, stamp_mtime)
+    }
+
+    /// The freshness decision against an already-read stamp and its file mtime.
+    ///
+    /// Split out from [`is_fresh`](Self::is_fresh) so the comparison can be exercised without
+    /// depending on the filesystem's mtime resolution.
+    fn is_fresh_against(&self, stamp: &BuildpackFingerprint, stamp_mtime: u64) -> bool {
+        self.target_triple == stamp.target_triple
+            && self.cargo_profile == stamp.cargo_profile
+            && self.manifest_hash == stamp.manifest_hash
+            && self.max_mtime < stamp_mtime
+    }
+
+    /// Reads a fingerprint previously written by [`write`](Self::write).
+    pub(crate) fn read(stamp_path: &Path) -> Option<BuildpackFingerprint> {
+        let contents = std::fs::read_to_string(stamp_path).ok()?;
+        let mut lines = contents.lines();
+
+        let max_mtime = lines.next()?.parse().ok()?;
+        let manifest_hash = lines.next()?.parse().ok()?;
+        let target_triple = lines.next()?.to_string();
+        let cargo_profile = match lines.next()? {
+            "dev" => CargoProfile::Dev,
+            "release" => CargoProfile::Release,
+            _ => return None,
+        };
+
+        Some(BuildpackFingerprint {
+            max_mtime,
+            manifest_hash,
+            target_triple,
+            cargo_profile,
+        })
+    }
+
+    /// Writes this fingerprint to `stamp_path`.
+    pub(crate) fn write(&self, stamp_path: &Path) -> std::io::Result<()> {
+        let profile = match self.cargo_profile {
+            CargoProfile::Dev => "dev",
+            CargoProfile::Release => "release",
+        };
+        std::fs::write(
+            stamp_path,
+            format!(
+                "{}\n{}\n{}\n{}\n",
+                self.max_mtime, self.manifest_hash, self.target_triple, profile
+            ),
+        )
+    }
+}
+
+/// Returns the cached buildpack artifact for these inputs, packaging it only if the cache is stale.
+///
+/// `package` is invoked to (re)produce the artifact at `buildpack_dir` when no fresh cache exists;
+/// on success the stamp next to it is refreshed so the next test with unchanged sources reuses the
+/// artifact and skips the `cargo build`. Any packaging or stamp error falls back to an
+/// unconditional rebuild on the following run.
+pub(crate) fn package_cached<E>(
+    cache_dir: &Path,
+    crate_dir: &Path,
+    target_triple: &str,
+    cargo_profile: CargoProfile,
+    package: impl FnOnce(&Path) -> Result<(), E>,
+) -> Result<PathBuf, E> {
+    let buildpack_dir = cache_dir.join("buildpack");
+    let stamp_path = cache_dir.join("fingerprint.stamp");
+
+    let fingerprint = BuildpackFingerprint::compute(crate_dir, target_triple, cargo_profile);
+
+    if buildpack_dir.is_dir()
+        && fingerprint
+            .as_ref()
+            .is_some_and(|fingerprint| fingerprint.is_fresh(&stamp_path))
+    {
+        return Ok(buildpack_dir);
+    }
+
+    // Drop the previous stamp first so that a failed packaging attempt leaves none behind.
+    let _ = std::fs::remove_file(&stamp_path);
+    package(&buildpack_dir)?;
+
+    // Only stamp once packaging succeeded, and only when we could compute a fingerprint; otherwise
+    // we leave no (or a removed) stamp so the next run rebuilds unconditionally.
+    match fingerprint {
+        Some(fingerprint) => {
+            let _ = fingerprint.write(&stamp_path);
+        }
+        None => {
+            let _ = std::fs::remove_file(&stamp_path);
+        }
+    }
+
+    Ok(buildpack_dir)
+}
+
+fn mtime_secs(path: &Path) -> Option<u64> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+fn collect_sources(dir: &Path, inputs: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_sources(&path, inputs);
+        } else {
+            inputs.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_crate(dir: &Path) {
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src").join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"").unwrap();
+        fs::write(dir.join("buildpack.toml"), "api = \"0.8\"").unwrap();
+    }
+
+    #[test]
+    fn compute_reflects_manifest_contents() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+
+        let first =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+
+        fs::write(tmp.path().join("Cargo.toml"), "[package]\nname = \"y\"").unwrap();
+        let second =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+
+        assert_ne!(first.manifest_hash, second.manifest_hash);
+    }
+
+    #[test]
+    fn unchanged_inputs_are_fresh() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+
+        let fingerprint =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+
+        // The stamp is written strictly after the inputs, so its mtime is one second newer. The
+        // same fingerprint must then be fresh — this is the regression the review caught, where
+        // comparing two equal input-max values made every run dirty.
+        let stamp = BuildpackFingerprint::compute(
+            tmp.path(),
+            "x86_64-unknown-linux-musl",
+            CargoProfile::Dev,
+        )
+        .unwrap();
+        let stamp_mtime = fingerprint.max_mtime + 1;
+
+        assert!(fingerprint.is_fresh_against(&stamp, stamp_mtime));
+    }
+
+    #[test]
+    fn input_at_or_after_stamp_is_dirty() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+        let fingerprint =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+
+        // Equal mtime (coarse-granularity same-second edit) and a newer input both count as dirty.
+        assert!(!fingerprint.is_fresh_against(&fingerprint, fingerprint.max_mtime));
+        assert!(!fingerprint.is_fresh_against(&fingerprint, fingerprint.max_mtime.saturating_sub(1)));
+    }
+
+    #[test]
+    fn profile_or_triple_or_manifest_change_is_dirty() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+        let stamp =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+        let stamp_mtime = stamp.max_mtime + 1;
+
+        let release = BuildpackFingerprint::compute(
+            tmp.path(),
+            "x86_64-unknown-linux-musl",
+            CargoProfile::Release,
+        )
+        .unwrap();
+        assert!(!release.is_fresh_against(&stamp, stamp_mtime));
+
+        let other_triple =
+            BuildpackFingerprint::compute(tmp.path(), "aarch64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+        assert!(!other_triple.is_fresh_against(&stamp, stamp_mtime));
+
+        fs::write(tmp.path().join("Cargo.toml"), "[package]\nname = \"z\"").unwrap();
+        let changed_manifest =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+        assert!(!changed_manifest.is_fresh_against(&stamp, stamp_mtime));
+    }
+
+    #[test]
+    fn write_then_read_roundtrips() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+        let stamp_path = tmp.path().join("fingerprint.stamp");
+
+        let fingerprint = BuildpackFingerprint::compute(
+            tmp.path(),
+            "x86_64-unknown-linux-musl",
+            CargoProfile::Release,
+        )
+        .unwrap();
+        fingerprint.write(&stamp_path).unwrap();
+
+        assert_eq!(BuildpackFingerprint::read(&stamp_path), Some(fingerprint));
+    }
+
+    #[test]
+    fn missing_stamp_is_dirty() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_crate(tmp.path());
+        let fingerprint =
+            BuildpackFingerprint::compute(tmp.path(), "x86_64-unknown-linux-musl", CargoProfile::Dev)
+                .unwrap();
+
+        assert!(!fingerprint.is_fresh(&tmp.path().join("does-not-exist.stamp")));
+    }
+}
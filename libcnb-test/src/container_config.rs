@@ -0,0 +1,148 @@
+use bollard::container::Config;
+use bollard::service::PortBinding;
+use std::collections::HashMap;
+
+/// Configuration for starting a container from a built image.
+///
+/// Allows overriding the entrypoint and command, setting environment variables and exposing ports.
+/// Used by [`TestContext::run_shell_command`](crate::TestContext::run_shell_command). The
+/// [`PrepareContainerContext`](crate::PrepareContainerContext) does not take a [`ContainerConfig`].
+#[derive(Clone, Default)]
+pub struct ContainerConfig {
+    pub(crate) entrypoint: Option<Vec<String>>,
+    pub(crate) command: Option<Vec<String>>,
+    pub(crate) env: HashMap<String, String>,
+    pub(crate) exposed_ports: Vec<u16>,
+}
+
+impl ContainerConfig {
+    /// Creates an empty container configuration that starts the image's default process.
+    #[must_use]
+    pub fn new() -> Self {
+        ContainerConfig::default()
+    }
+
+    /// Overrides the container entrypoint.
+    pub fn entrypoint(&mut self, entrypoint: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.entrypoint = Some(entrypoint.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Overrides the container command.
+    pub fn command(&mut self, command: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.command = Some(command.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Inserts or updates an environment variable for the container.
+    pub fn env(&mut self, k: impl Into<String>, v: impl Into<String>) -> &mut Self {
+        self.env.insert(k.into(), v.into());
+        self
+    }
+
+    /// Exposes a container port, mapping it to a random port on the host.
+    pub fn expose_port(&mut self, port: u16) -> &mut Self {
+        self.exposed_ports.push(port);
+        self
+    }
+
+    /// Builds the `bollard` container configuration for the given image, applying the entrypoint,
+    /// command, environment variables and exposed ports set on this [`ContainerConfig`].
+    pub(crate) fn to_docker_config(&self, image_name: &str) -> Config<String> {
+        let exposed_ports = self
+            .exposed_ports
+            .iter()
+            .map(|port| (format!("{port}/tcp"), HashMap::new()))
+            .collect::<HashMap<_, _>>();
+
+        let port_bindings = self
+            .exposed_ports
+            .iter()
+            .map(|port| {
+                (
+                    format!("{port}/tcp"),
+                    Some(vec![PortBinding {
+                        host_ip: Some(String::from("127.0.0.1")),
+                        // An empty host port lets Docker assign a random free port on the host.
+                        host_port: Some(String::new()),
+                    }]),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Config {
+            image: Some(image_name.to_string()),
+            entrypoint: self.entrypoint.clone(),
+            cmd: self.command.clone(),
+            env: Some(
+                self.env
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect(),
+            ),
+            exposed_ports: (!exposed_ports.is_empty()).then_some(exposed_ports),
+            host_config: (!port_bindings.is_empty()).then_some(bollard::service::HostConfig {
+                port_bindings: Some(port_bindings),
+                ..bollard::service::HostConfig::default()
+            }),
+            ..Config::default()
+        }
+    }
+}
+
+/// Captured output of a command run in a container.
+///
+/// Returned by [`TestContext::run_shell_command`](crate::TestContext::run_shell_command).
+#[derive(Clone, Debug)]
+pub struct ContainerOutput {
+    /// Standard output of the command, interpreted as an UTF-8 string.
+    pub stdout: String,
+    /// Standard error of the command, interpreted as an UTF-8 string.
+    pub stderr: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_populates_all_fields() {
+        let mut config = ContainerConfig::new();
+        config
+            .entrypoint(["/bin/sh"])
+            .command(["-c", "echo hi"])
+            .env("FOO", "bar")
+            .expose_port(8080);
+
+        assert_eq!(config.entrypoint, Some(vec![String::from("/bin/sh")]));
+        assert_eq!(
+            config.command,
+            Some(vec![String::from("-c"), String::from("echo hi")])
+        );
+        assert_eq!(config.env.get("FOO"), Some(&String::from("bar")));
+        assert_eq!(config.exposed_ports, vec![8080]);
+    }
+
+    #[test]
+    fn to_docker_config_applies_the_config() {
+        let mut config = ContainerConfig::new();
+        config
+            .entrypoint(["/bin/sh"])
+            .command(["-c", "echo hi"])
+            .env("FOO", "bar")
+            .expose_port(8080);
+
+        let docker_config = config.to_docker_config("my-image");
+
+        assert_eq!(docker_config.image, Some(String::from("my-image")));
+        assert_eq!(docker_config.entrypoint, Some(vec![String::from("/bin/sh")]));
+        assert_eq!(
+            docker_config.cmd,
+            Some(vec![String::from("-c"), String::from("echo hi")])
+        );
+        assert_eq!(docker_config.env, Some(vec![String::from("FOO=bar")]));
+        assert!(docker_config
+            .exposed_ports
+            .is_some_and(|ports| ports.contains_key("8080/tcp")));
+    }
+}
@@ -0,0 +1,71 @@
+use bollard::Docker;
+
+/// Connects to the Docker daemon, honoring `DOCKER_HOST` when it points at a remote endpoint.
+///
+/// When `DOCKER_HOST` is unset or points at a unix socket, this connects to the local daemon using
+/// bollard's defaults. When it is a `tcp://` or `https://` URL, the connection is made to that
+/// endpoint instead, honoring `DOCKER_CERT_PATH` and `DOCKER_TLS_VERIFY` for the TLS case. This
+/// allows running integration tests against a daemon that is not reachable via a local unix socket,
+/// such as in Docker-in-Docker or rootless-remote setups.
+///
+/// # Panics
+/// - When a connection to the configured Docker daemon could not be established.
+pub(crate) fn connect() -> Docker {
+    match std::env::var("DOCKER_HOST") {
+        Ok(docker_host) if is_remote_host(&docker_host) => connect_remote(&docker_host),
+        _ => Docker::connect_with_local_defaults().expect("Could not connect to local Docker daemon"),
+    }
+}
+
+/// Whether a `DOCKER_HOST` value refers to a remote daemon rather than a local unix socket.
+fn is_remote_host(docker_host: &str) -> bool {
+    docker_host.starts_with("tcp://") || docker_host.starts_with("https://")
+}
+
+fn connect_remote(docker_host: &str) -> Docker {
+    // A `tcp://` endpoint combined with `DOCKER_TLS_VERIFY` is TLS-secured, matching the Docker
+    // CLI's behaviour; an `https://` endpoint always is.
+    let tls =
+        docker_host.starts_with("https://") || std::env::var_os("DOCKER_TLS_VERIFY").is_some();
+
+    if tls {
+        let cert_path = std::env::var("DOCKER_CERT_PATH")
+            .expect("DOCKER_CERT_PATH must be set to connect to a TLS-secured Docker daemon");
+        let cert_path = std::path::Path::new(&cert_path);
+
+        // bollard expects a `tcp://` address; a `DOCKER_HOST` of `https://host:port` carries the
+        // same host and port, so normalize the scheme before handing it over.
+        let ssl_address = docker_host.replacen("https://", "tcp://", 1);
+
+        Docker::connect_with_ssl(
+            &ssl_address,
+            &cert_path.join("key.pem"),
+            &cert_path.join("cert.pem"),
+            &cert_path.join("ca.pem"),
+            120,
+            bollard::API_DEFAULT_VERSION,
+        )
+        .expect("Could not connect to remote Docker daemon via TLS")
+    } else {
+        Docker::connect_with_http(docker_host, 120, bollard::API_DEFAULT_VERSION)
+            .expect("Could not connect to remote Docker daemon")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_remote_host;
+
+    #[test]
+    fn detects_remote_hosts() {
+        assert!(is_remote_host("tcp://192.0.2.1:2376"));
+        assert!(is_remote_host("https://docker.example.com:2376"));
+    }
+
+    #[test]
+    fn treats_local_sockets_as_not_remote() {
+        assert!(!is_remote_host("unix:///var/run/docker.sock"));
+        assert!(!is_remote_host("npipe:////./pipe/docker_engine"));
+        assert!(!is_remote_host(""));
+    }
+}
@@ -8,17 +8,22 @@
 
 mod app;
 mod build;
+mod build_cache;
+mod container_config;
 mod container_context;
 mod container_port_mapping;
+mod docker;
 mod log;
 mod macros;
 mod pack;
 mod runner;
+mod sbom;
 mod util;
 
+pub use crate::container_config::{ContainerConfig, ContainerOutput};
 pub use crate::container_context::{ContainerContext, PrepareContainerContext};
-use crate::pack::{PackBuildCommand, PullPolicy};
 pub use crate::runner::TestRunner;
+pub use crate::sbom::{Sbom, SbomFiles, SbomFormat, SbomType};
 use bollard::image::RemoveImageOptions;
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
@@ -32,6 +37,72 @@ pub struct TestConfig {
     buildpacks: Vec<BuildpackReference>,
     env: HashMap<String, String>,
     app_dir_preprocessor: Option<Box<dyn Fn(PathBuf)>>,
+    expected_pack_result: PackResult,
+    cargo_profile: CargoProfile,
+}
+
+/// The Cargo build profile used to compile the crate-under-test buildpack.
+///
+/// Used with [`TestConfig::cargo_profile`] to exercise the buildpack exactly as it ships, since
+/// optimized binaries can behave differently (panic formatting, stripped symbols, timing).
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum CargoProfile {
+    /// The default `dev` profile.
+    Dev,
+    /// The `release` profile, i.e. `--release`.
+    Release,
+}
+
+impl CargoProfile {
+    /// The extra `cargo build` arguments that select this profile.
+    ///
+    /// Threaded into the packaging command so the compiled buildpack artifact matches the requested
+    /// profile: the `dev` profile is the Cargo default and needs no flag, while `release` adds
+    /// `--release`.
+    pub(crate) fn cargo_args(self) -> &'static [&'static str] {
+        match self {
+            CargoProfile::Dev => &[],
+            CargoProfile::Release => &["--release"],
+        }
+    }
+}
+
+/// Whether a `pack` build is expected to succeed or fail.
+///
+/// Used with [`TestConfig::expected_pack_result`] to test a buildpack's error paths, such as detect
+/// rejection or build-time failures.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum PackResult {
+    /// The `pack` build is expected to succeed. The runner panics if it fails.
+    Success,
+    /// The `pack` build is expected to fail. The runner panics if it unexpectedly succeeds, but not
+    /// on a non-zero `pack` exit. The test closure still receives a [`TestContext`] with populated
+    /// `pack_stdout`/`pack_stderr` so the emitted diagnostics can be asserted on.
+    Failure,
+}
+
+impl PackResult {
+    /// Validates the outcome of a `pack` build against this expectation, panicking on a mismatch.
+    ///
+    /// Called by the runner after `pack` exits. For [`PackResult::Success`] a non-zero exit aborts
+    /// the test as before; for [`PackResult::Failure`] a non-zero exit is expected and tolerated,
+    /// and only an unexpected success aborts the test. In both cases the captured `pack` output is
+    /// included in the panic message to aid debugging.
+    pub(crate) fn validate(self, pack_succeeded: bool, pack_stdout: &str, pack_stderr: &str) {
+        match self {
+            PackResult::Success if !pack_succeeded => {
+                panic!(
+                    "Expected `pack` build to succeed, but it failed!\n\n## stdout\n\n{pack_stdout}\n\n## stderr\n\n{pack_stderr}\n"
+                );
+            }
+            PackResult::Failure if pack_succeeded => {
+                panic!(
+                    "Expected `pack` build to fail, but it succeeded!\n\n## stdout\n\n{pack_stdout}\n\n## stderr\n\n{pack_stderr}\n"
+                );
+            }
+            _ => {}
+        }
+    }
 }
 
 /// References a Cloud Native Buildpack
@@ -57,6 +128,8 @@ impl TestConfig {
             buildpacks: vec![BuildpackReference::Crate],
             env: HashMap::new(),
             app_dir_preprocessor: None,
+            expected_pack_result: PackResult::Success,
+            cargo_profile: CargoProfile::Dev,
         }
     }
 
@@ -154,6 +227,53 @@ impl TestConfig {
         self.app_dir_preprocessor = Some(Box::new(f));
         self
     }
+
+    /// Sets the expected result of the `pack` build.
+    ///
+    /// Defaults to [`PackResult::Success`]. Set to [`PackResult::Failure`] to test a buildpack's
+    /// error paths, such as detect rejection or a build-time failure. In that case the runner will
+    /// panic only if the build unexpectedly succeeds, and the test closure can assert on the
+    /// diagnostics emitted to `pack_stdout`/`pack_stderr`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libcnb_test::{assert_contains, PackResult, TestConfig, TestRunner};
+    ///
+    /// TestRunner::default().run_test(
+    ///     TestConfig::new("heroku/builder:22", "test-fixtures/invalid-app")
+    ///         .expected_pack_result(PackResult::Failure),
+    ///     |context| {
+    ///         assert_contains!(context.pack_stderr, "No valid Ruby version found");
+    ///     },
+    /// );
+    /// ```
+    pub fn expected_pack_result(&mut self, pack_result: PackResult) -> &mut Self {
+        self.expected_pack_result = pack_result;
+        self
+    }
+
+    /// Sets the Cargo profile used to compile the crate-under-test buildpack.
+    ///
+    /// Defaults to [`CargoProfile::Dev`]. Set to [`CargoProfile::Release`] to compile the buildpack
+    /// with optimizations, matching how it ships in production. The selected profile is threaded
+    /// through to the compiled artifact placed into the packaged buildpack.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libcnb_test::{CargoProfile, TestConfig, TestRunner};
+    ///
+    /// TestRunner::default().run_test(
+    ///     TestConfig::new("heroku/builder:22", "test-fixtures/app")
+    ///         .cargo_profile(CargoProfile::Release),
+    ///     |context| {
+    ///         // ...
+    ///     },
+    /// );
+    /// ```
+    pub fn cargo_profile(&mut self, cargo_profile: CargoProfile) -> &mut Self {
+        self.cargo_profile = cargo_profile;
+        self
+    }
 }
 
 /// Context for a currently executing test.
@@ -201,6 +321,144 @@ impl<'a> TestContext<'a> {
         PrepareContainerContext::new(self)
     }
 
+    /// Downloads the Software Bill of Materials of the image built by this test.
+    ///
+    /// This shells out to `pack sbom download` and parses the resulting CycloneDX, SPDX and syft
+    /// JSON documents, keyed by buildpack id, layer name and [`SbomFormat`]. Both launch- and
+    /// build-scoped SBOMs are returned, allowing tests to assert that a buildpack actually recorded
+    /// the dependencies it installed.
+    ///
+    /// # Panics
+    /// - When the `pack sbom download` command could not be run or fails.
+    /// - When a downloaded SBOM document could not be read or parsed.
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// use libcnb_test::{SbomFormat, SbomType, TestConfig, TestRunner};
+    ///
+    /// TestRunner::default().run_test(
+    ///     TestConfig::new("heroku/builder:22", "test-fixtures/app"),
+    ///     |context| {
+    ///         let sbom_files = context.download_sbom_files();
+    ///         assert!(sbom_files
+    ///             .get(SbomType::Launch, "heroku/ruby", "gems", SbomFormat::CycloneDx)
+    ///             .is_some());
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub fn download_sbom_files(&self) -> SbomFiles {
+        sbom::download_sbom_files(&self.image_name)
+    }
+
+    /// Runs a one-off shell command in a container from the image built by this test.
+    ///
+    /// The command is run with `/bin/sh -c <command>`. This starts a container, waits for the
+    /// command to exit and returns its captured standard output and error. This is a convenience
+    /// for the common "build image, then assert on the output of running a command in it" pattern,
+    /// removing the boilerplate of manually wiring up container exec and log streaming.
+    ///
+    /// [`prepare_container`](Self::prepare_container) does not take a [`ContainerConfig`]; it
+    /// starts the image's default process.
+    ///
+    /// # Panics
+    /// - When the container could not be created or started, or its logs could not be read.
+    ///   Errors reported while waiting for the container to exit are ignored.
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// use libcnb_test::{assert_contains, TestConfig, TestRunner};
+    ///
+    /// TestRunner::default().run_test(
+    ///     TestConfig::new("heroku/builder:22", "test-fixtures/app"),
+    ///     |context| {
+    ///         let output = context.run_shell_command("ruby --version");
+    ///         assert_contains!(output.stdout, "ruby 3.1");
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub fn run_shell_command(&self, command: impl Into<String>) -> ContainerOutput {
+        let mut config = ContainerConfig::new();
+        config
+            .entrypoint(["/bin/sh"])
+            .command(["-c", &command.into()]);
+
+        self.start_container_and_wait_for_output(&config)
+    }
+
+    /// Creates and starts a container from the given [`ContainerConfig`], waits for it to exit and
+    /// returns its captured standard output and error.
+    ///
+    /// # Panics
+    /// - When the container could not be created or started, or its logs could not be read.
+    ///   Errors reported while waiting for the container to exit are ignored.
+    fn start_container_and_wait_for_output(&self, config: &ContainerConfig) -> ContainerOutput {
+        use bollard::container::{
+            CreateContainerOptions, LogOutput, LogsOptions, RemoveContainerOptions,
+            WaitContainerOptions,
+        };
+        use futures::StreamExt;
+
+        let docker = &self.runner.docker;
+
+        self.runner.tokio_runtime.block_on(async {
+            let container = docker
+                .create_container(
+                    None::<CreateContainerOptions<String>>,
+                    config.to_docker_config(&self.image_name),
+                )
+                .await
+                .expect("Could not create container");
+
+            docker
+                .start_container::<String>(&container.id, None)
+                .await
+                .expect("Could not start container");
+
+            let mut wait_stream =
+                docker.wait_container(&container.id, None::<WaitContainerOptions<String>>);
+            while wait_stream.next().await.is_some() {}
+
+            let mut logs_stream = docker.logs(
+                &container.id,
+                Some(LogsOptions::<String> {
+                    stdout: true,
+                    stderr: true,
+                    ..LogsOptions::default()
+                }),
+            );
+
+            let mut stdout = Vec::new();
+            let mut stderr = Vec::new();
+            while let Some(log) = logs_stream.next().await {
+                match log.expect("Could not read container logs") {
+                    LogOutput::StdOut { message } => stdout.extend_from_slice(&message),
+                    LogOutput::StdErr { message } => stderr.extend_from_slice(&message),
+                    _ => {}
+                }
+            }
+
+            // The container has already exited; removal failures are not relevant to the test.
+            let _remove_result = docker
+                .remove_container(
+                    &container.id,
+                    Some(RemoveContainerOptions {
+                        force: true,
+                        ..RemoveContainerOptions::default()
+                    }),
+                )
+                .await;
+
+            ContainerOutput {
+                stdout: String::from_utf8_lossy(&stdout).to_string(),
+                stderr: String::from_utf8_lossy(&stderr).to_string(),
+            }
+        })
+    }
+
     /// Starts a subsequent integration test run.
     ///
     /// This function behaves exactly like [`TestRunner::run_test`], but it will reuse the OCI image
@@ -261,3 +519,32 @@ impl<'a> Drop for TestContext<'a> {
 #[cfg(doctest)]
 #[doc = include_str!("../README.md")]
 pub struct ReadmeDoctests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_result_success_accepts_success_and_failure_is_tolerated_on_failure() {
+        PackResult::Success.validate(true, "", "");
+        PackResult::Failure.validate(false, "", "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected `pack` build to succeed, but it failed!")]
+    fn pack_result_success_panics_on_failure() {
+        PackResult::Success.validate(false, "build output", "error output");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected `pack` build to fail, but it succeeded!")]
+    fn pack_result_failure_panics_on_success() {
+        PackResult::Failure.validate(true, "build output", "");
+    }
+
+    #[test]
+    fn cargo_profile_maps_to_cargo_args() {
+        assert_eq!(CargoProfile::Dev.cargo_args(), &[] as &[&str]);
+        assert_eq!(CargoProfile::Release.cargo_args(), &["--release"]);
+    }
+}
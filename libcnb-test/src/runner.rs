@@ -0,0 +1,153 @@
+use crate::{docker, BuildpackReference, TestConfig, TestContext};
+use bollard::Docker;
+use std::borrow::BorrowMut;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::runtime::Runtime;
+
+/// Runs integration tests against a Docker daemon using `pack`.
+pub struct TestRunner {
+    pub(crate) docker: Docker,
+    pub(crate) tokio_runtime: Runtime,
+}
+
+impl Default for TestRunner {
+    fn default() -> Self {
+        TestRunner {
+            // Honors `DOCKER_HOST` so tests can target a remote daemon, falling back to the local
+            // socket otherwise.
+            docker: docker::connect(),
+            tokio_runtime: Runtime::new().expect("Could not create Tokio runtime"),
+        }
+    }
+}
+
+impl TestRunner {
+    /// Runs a test against a freshly built image.
+    ///
+    /// See [`TestConfig`] for the available settings and [`TestContext`] for what the test closure
+    /// receives.
+    ///
+    /// # Panics
+    /// - When the app could not be copied.
+    /// - When this crate could not be packaged as a buildpack.
+    /// - When the `pack` command result does not match the configured
+    ///   [`expected_pack_result`](TestConfig::expected_pack_result).
+    pub fn run_test<F: FnOnce(TestContext), T: BorrowMut<TestConfig>>(&self, config: T, f: F) {
+        self.run_test_internal(generate_image_name(), config, f);
+    }
+
+    pub(crate) fn run_test_internal<F: FnOnce(TestContext), T: BorrowMut<TestConfig>>(
+        &self,
+        image_name: String,
+        mut config: T,
+        f: F,
+    ) {
+        let config = config.borrow_mut();
+
+        let cargo_manifest_dir = PathBuf::from(
+            std::env::var("CARGO_MANIFEST_DIR").expect("Could not determine Cargo manifest dir"),
+        );
+
+        let app_dir = if config.app_dir.is_relative() {
+            cargo_manifest_dir.join(&config.app_dir)
+        } else {
+            config.app_dir.clone()
+        };
+
+        let temp_app_dir =
+            copy_app_dir(&app_dir).expect("Could not copy app directory for integration test");
+        if let Some(preprocessor) = &config.app_dir_preprocessor {
+            preprocessor(temp_app_dir.clone());
+        }
+
+        let buildpack_dir = crate::build::package_crate_buildpack(
+            &cargo_manifest_dir,
+            &config.target_triple,
+            config.cargo_profile,
+        );
+
+        let mut command = Command::new("pack");
+        command
+            .args(["build", &image_name, "--builder", &config.builder_name])
+            .arg("--path")
+            .arg(&temp_app_dir);
+
+        for buildpack in &config.buildpacks {
+            match buildpack {
+                BuildpackReference::Crate => {
+                    command.arg("--buildpack").arg(&buildpack_dir);
+                }
+                BuildpackReference::Other(id) => {
+                    command.args(["--buildpack", id]);
+                }
+            }
+        }
+
+        for (key, value) in &config.env {
+            command.args(["--env", &format!("{key}={value}")]);
+        }
+        command.args(["--pull-policy", "if-not-present"]);
+
+        let (pack_stdout, pack_stderr) = run_pack(&mut command, config.expected_pack_result);
+
+        let context = TestContext {
+            pack_stdout,
+            pack_stderr,
+            app_dir: temp_app_dir,
+            image_name,
+            runner: self,
+        };
+
+        f(context);
+    }
+}
+
+/// Runs the assembled `pack build` command and validates its exit status against `expected_result`.
+///
+/// Rather than unconditionally panicking on a non-zero exit, the outcome is handed to
+/// [`PackResult::validate`](crate::PackResult::validate): a [`Failure`](crate::PackResult::Failure)
+/// expectation tolerates a failing build (and panics only on an unexpected success), while the
+/// default [`Success`](crate::PackResult::Success) expectation still aborts the test on failure.
+/// The captured stdout/stderr are returned so the test closure can assert on the diagnostics.
+fn run_pack(command: &mut Command, expected_result: crate::PackResult) -> (String, String) {
+    let output = command.output().expect("Could not spawn `pack build` command");
+    let pack_stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let pack_stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+    expected_result.validate(output.status.success(), &pack_stdout, &pack_stderr);
+
+    (pack_stdout, pack_stderr)
+}
+
+/// Recursively copies the app directory into a unique temporary directory, returning its path.
+fn copy_app_dir(app_dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    let target = std::env::temp_dir().join(generate_image_name());
+    copy_dir_recursive(app_dir, &target)?;
+    Ok(target)
+}
+
+fn copy_dir_recursive(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&entry.path(), &destination)?;
+        } else {
+            std::fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Generates a unique image name for an integration test run.
+fn generate_image_name() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "libcnb-test-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    )
+}
@@ -0,0 +1,261 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// The scope a Software Bill of Materials was recorded for.
+///
+/// The CNB lifecycle emits SBOMs both for layers that end up in the final image (launch) and for
+/// layers that only exist during the build (build).
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum SbomType {
+    /// SBOMs recorded for launch-scoped layers, i.e. layers present in the resulting image.
+    Launch,
+    /// SBOMs recorded for build-scoped layers.
+    Build,
+}
+
+/// A Software Bill of Materials format as defined by the buildpack spec.
+///
+/// The lifecycle writes one file per format a buildpack decided to emit, so a single layer can be
+/// described by more than one [`SbomFormat`] at the same time.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum SbomFormat {
+    /// CycloneDX, written as `*.sbom.cdx.json`.
+    CycloneDx,
+    /// SPDX, written as `*.sbom.spdx.json`.
+    Spdx,
+    /// Syft, written as `*.sbom.syft.json`.
+    Syft,
+}
+
+impl SbomFormat {
+    /// The file extension (without the leading `sbom.`) the lifecycle uses for this format.
+    pub(crate) fn file_extension(self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+            SbomFormat::Syft => "syft.json",
+        }
+    }
+}
+
+/// A single Software Bill of Materials document recorded by a buildpack.
+///
+/// Holds both the raw bytes as written by the lifecycle and a parsed JSON view, so tests can either
+/// assert on the exact serialization or navigate the document structurally.
+#[derive(Clone, Debug)]
+pub struct Sbom {
+    /// The raw bytes of the SBOM document, exactly as written by the lifecycle.
+    pub raw: Vec<u8>,
+    /// The SBOM document parsed as JSON.
+    pub parsed: Value,
+}
+
+/// All Software Bill of Materials documents downloaded from a built image.
+///
+/// Documents are keyed by buildpack id, layer name and [`SbomFormat`], scoped by [`SbomType`]. Use
+/// [`SbomFiles::get`] to look up a specific document.
+#[derive(Clone, Debug, Default)]
+pub struct SbomFiles {
+    files: HashMap<(SbomType, String, String, SbomFormat), Sbom>,
+}
+
+impl SbomFiles {
+    /// Returns the SBOM document recorded for the given buildpack id, layer name and format, if any.
+    #[must_use]
+    pub fn get(
+        &self,
+        sbom_type: SbomType,
+        buildpack_id: impl AsRef<str>,
+        layer_name: impl AsRef<str>,
+        format: SbomFormat,
+    ) -> Option<&Sbom> {
+        self.files.get(&(
+            sbom_type,
+            on_disk_buildpack_id(buildpack_id.as_ref()),
+            layer_name.as_ref().to_string(),
+            format,
+        ))
+    }
+
+    /// The number of downloaded SBOM documents across all scopes, layers and formats.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether no SBOM documents were recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Downloads and parses the SBOM tree of the given image using `pack sbom download`.
+///
+/// # Panics
+/// - When the `pack sbom download` command could not be spawned or exits non-zero.
+/// - When a downloaded SBOM document could not be read or parsed as JSON.
+pub(crate) fn download_sbom_files(image_name: &str) -> SbomFiles {
+    let output_dir = tempfile::tempdir().expect("Could not create temporary SBOM output directory");
+
+    let output = Command::new("pack")
+        .args(["sbom", "download", image_name, "--output-dir"])
+        .arg(output_dir.path())
+        .output()
+        .expect("Could not spawn `pack sbom download` command");
+
+    assert!(
+        output.status.success(),
+        "`pack sbom download` failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    parse_sbom_tree(&output_dir.path().join("layers").join("sbom"))
+}
+
+/// Parses the SBOM tree rooted at `sbom_root` as written by `pack sbom download`.
+///
+/// The lifecycle lays out SBOMs as `<sbom-root>/<scope>/<buildpack-id>/<layer>/sbom.<ext>`, where
+/// the buildpack id has its `/` replaced by `_`. Scopes or files that are missing are skipped.
+fn parse_sbom_tree(sbom_root: &Path) -> SbomFiles {
+    let mut files = HashMap::new();
+
+    for (sbom_type, scope_dir) in [(SbomType::Launch, "launch"), (SbomType::Build, "build")] {
+        let scope_root = sbom_root.join(scope_dir);
+        if !scope_root.is_dir() {
+            continue;
+        }
+
+        for buildpack_entry in read_dir(&scope_root) {
+            // The on-disk directory name is kept verbatim as the key. Reconstructing the buildpack
+            // id with a blanket `_` -> `/` replacement would corrupt ids that legitimately contain
+            // `_` (e.g. `heroku/foo_bar`), so lookups normalize the *queried* id the same way the
+            // lifecycle encodes it on disk instead (see `on_disk_buildpack_id`).
+            let buildpack_id = buildpack_entry.file_name().to_string_lossy().to_string();
+            let buildpack_path = buildpack_entry.path();
+            if !buildpack_path.is_dir() {
+                continue;
+            }
+
+            for layer_entry in read_dir(&buildpack_path) {
+                let layer_name = layer_entry.file_name().to_string_lossy().to_string();
+                let layer_path = layer_entry.path();
+                if !layer_path.is_dir() {
+                    continue;
+                }
+
+                for format in [SbomFormat::CycloneDx, SbomFormat::Spdx, SbomFormat::Syft] {
+                    let sbom_path = layer_path.join(format!("sbom.{}", format.file_extension()));
+                    if let Some(sbom) = read_sbom(&sbom_path) {
+                        files.insert(
+                            (sbom_type, buildpack_id.clone(), layer_name.clone(), format),
+                            sbom,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    SbomFiles { files }
+}
+
+/// Encodes a buildpack id the way the lifecycle lays it out on disk, replacing each `/` with `_`.
+///
+/// A queried id maps to exactly one on-disk directory name even when it contains `_`. Ids that
+/// differ only in `/` versus `_` (e.g. `heroku/foo_bar` and `heroku/foo/bar`) map to the same
+/// directory name and therefore to the same documents.
+fn on_disk_buildpack_id(buildpack_id: &str) -> String {
+    buildpack_id.replace('/', "_")
+}
+
+fn read_dir(path: &Path) -> impl Iterator<Item = std::fs::DirEntry> {
+    std::fs::read_dir(path)
+        .unwrap_or_else(|error| panic!("Could not read SBOM directory {path:?}: {error}"))
+        .map(|entry| entry.expect("Could not read SBOM directory entry"))
+}
+
+fn read_sbom(path: &Path) -> Option<Sbom> {
+    if !path.is_file() {
+        return None;
+    }
+
+    let raw = std::fs::read(path)
+        .unwrap_or_else(|error| panic!("Could not read SBOM document {path:?}: {error}"));
+    let parsed = serde_json::from_slice(&raw)
+        .unwrap_or_else(|error| panic!("Could not parse SBOM document {path:?}: {error}"));
+
+    Some(Sbom { raw, parsed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_sbom(dir: &Path, scope: &str, buildpack_id: &str, layer: &str, ext: &str, json: &str) {
+        let layer_dir = dir.join(scope).join(buildpack_id).join(layer);
+        fs::create_dir_all(&layer_dir).unwrap();
+        fs::write(layer_dir.join(format!("sbom.{ext}")), json).unwrap();
+    }
+
+    #[test]
+    fn parses_and_keys_the_sbom_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        // Buildpack id `heroku_ruby` on disk must map back to `heroku/ruby`.
+        write_sbom(root, "launch", "heroku_ruby", "gems", "cdx.json", r#"{"a":1}"#);
+        write_sbom(root, "build", "heroku_ruby", "bundler", "spdx.json", r#"{"b":2}"#);
+
+        let files = parse_sbom_tree(root);
+
+        assert_eq!(files.len(), 2);
+
+        let launch = files
+            .get(SbomType::Launch, "heroku/ruby", "gems", SbomFormat::CycloneDx)
+            .expect("launch CycloneDX SBOM should be present");
+        assert_eq!(launch.raw, br#"{"a":1}"#);
+        assert_eq!(launch.parsed, serde_json::json!({"a": 1}));
+
+        assert!(files
+            .get(SbomType::Build, "heroku/ruby", "bundler", SbomFormat::Spdx)
+            .is_some());
+
+        // Formats and scopes that were not written must be absent.
+        assert!(files
+            .get(SbomType::Launch, "heroku/ruby", "gems", SbomFormat::Syft)
+            .is_none());
+        assert!(files
+            .get(SbomType::Build, "heroku/ruby", "gems", SbomFormat::CycloneDx)
+            .is_none());
+    }
+
+    #[test]
+    fn buildpack_id_with_underscore_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        // On-disk `heroku_foo_bar` is the encoding of id `heroku/foo_bar`; a blanket `_` -> `/`
+        // replacement would corrupt it to `heroku/foo/bar` and the lookup would miss.
+        write_sbom(root, "launch", "heroku_foo_bar", "deps", "cdx.json", r#"{"ok":true}"#);
+
+        let files = parse_sbom_tree(root);
+
+        assert!(files
+            .get(SbomType::Launch, "heroku/foo_bar", "deps", SbomFormat::CycloneDx)
+            .is_some());
+        assert!(files
+            .get(SbomType::Launch, "heroku/foo/bar", "deps", SbomFormat::CycloneDx)
+            .is_none());
+    }
+
+    #[test]
+    fn missing_scopes_yield_empty_result() {
+        let tmp = tempfile::tempdir().unwrap();
+        let files = parse_sbom_tree(tmp.path());
+        assert!(files.is_empty());
+    }
+}
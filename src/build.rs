@@ -1,5 +1,8 @@
 use crate::{
-    data::{buildpack::BuildpackToml, buildpack_plan::BuildpackPlan, launch::Launch},
+    data::{
+        build::BuildToml, buildpack::BuildpackToml, buildpack_plan::BuildpackPlan, launch::Launch,
+        store::Store,
+    },
     layer::Layer,
     platform::{GenericPlatform, Platform},
     shared::read_toml_file,
@@ -113,6 +116,74 @@ impl<P: Platform> BuildContext<P> {
 
         Ok(())
     }
+
+    /// Write the `build.toml` for this buildpack, declaring unmet requirements and build-time
+    /// environment contributions.
+    pub fn write_build(&self, data: BuildToml) -> Result<(), Error> {
+        let path = self.layers_dir.join("build.toml");
+        fs::write(path, toml::to_string(&data)?)?;
+
+        Ok(())
+    }
+
+    /// Write the `store.toml` for this buildpack, persisting metadata across builds.
+    pub fn write_store(&self, data: Store) -> Result<(), Error> {
+        let path = self.layers_dir.join("store.toml");
+        fs::write(path, toml::to_string(&data)?)?;
+
+        Ok(())
+    }
+
+    /// Write a Software Bill of Materials for the given scope into the layers directory.
+    ///
+    /// The document is written as `launch.sbom.<ext>` or `build.sbom.<ext>`, where the extension is
+    /// determined by the [`SbomFormat`].
+    pub fn write_sbom(
+        &self,
+        sbom_type: SbomType,
+        format: SbomFormat,
+        data: impl AsRef<[u8]>,
+    ) -> Result<(), Error> {
+        let scope = match sbom_type {
+            SbomType::Launch => "launch",
+            SbomType::Build => "build",
+        };
+        let path = self
+            .layers_dir
+            .join(format!("{}.sbom.{}", scope, format.file_extension()));
+        fs::write(path, data)?;
+
+        Ok(())
+    }
+}
+
+/// The scope a Software Bill of Materials is written for.
+pub enum SbomType {
+    /// Written as `launch.sbom.<ext>`, describing dependencies present in the resulting image.
+    Launch,
+    /// Written as `build.sbom.<ext>`, describing build-time dependencies.
+    Build,
+}
+
+/// A Software Bill of Materials format supported by the buildpack spec.
+pub enum SbomFormat {
+    /// CycloneDX, written with the `cdx.json` extension.
+    CycloneDx,
+    /// SPDX, written with the `spdx.json` extension.
+    Spdx,
+    /// Syft, written with the `syft.json` extension.
+    Syft,
+}
+
+impl SbomFormat {
+    /// The file extension used for documents in this format.
+    fn file_extension(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::Spdx => "spdx.json",
+            SbomFormat::Syft => "syft.json",
+        }
+    }
 }
 
 pub type GenericBuildContext = BuildContext<GenericPlatform>;